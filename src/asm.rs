@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use crate::memory::{Address, Memory, Value};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    UnknownMnemonic { line: usize, mnemonic: String },
+    UnknownLabel { line: usize, label: String },
+    InvalidOperand { line: usize, operand: String },
+    WrongOperandCount { line: usize, expected: usize, found: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OperandMode {
+    Positional,
+    Immediate,
+    Relative,
+}
+
+impl OperandMode {
+    fn digit(self) -> i32 {
+        match self {
+            OperandMode::Positional => 0,
+            OperandMode::Immediate => 1,
+            OperandMode::Relative => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Literal(OperandMode, i32),
+    Label(OperandMode, String),
+}
+
+impl Operand {
+    fn mode(&self) -> OperandMode {
+        match self {
+            Operand::Literal(mode, _) => *mode,
+            Operand::Label(mode, _) => *mode,
+        }
+    }
+}
+
+enum Directive {
+    Instruction {
+        opcode: i32,
+        operands: Vec<Operand>,
+    },
+    Data(Vec<i32>),
+}
+
+impl Directive {
+    fn width(&self) -> u32 {
+        match self {
+            Directive::Instruction { operands, .. } => operands.len() as u32 + 1,
+            Directive::Data(values) => values.len() as u32,
+        }
+    }
+}
+
+struct Line {
+    number: usize,
+    label: Option<String>,
+    directive: Directive,
+}
+
+/// Parses a small Intcode assembly language into `Memory`, ready to feed to
+/// `Processor::new`. Mnemonics mirror the `disasm` module's (`ADD`, `MUL`, `IN`,
+/// `OUT`, `JIT`, `JIF`, `LT`, `EQ`, `ARB`, `HALT`), operands are comma-separated and
+/// annotated for mode (`100` positional, `#5` immediate, `~3` relative), labels
+/// resolve to the address of the instruction or `DATA` block they precede, and
+/// `DATA n, n, ...` emits raw cells.
+pub fn assemble(source: &str) -> Result<Memory, Error> {
+    let lines = parse_lines(source)?;
+
+    let mut labels = HashMap::new();
+    let mut address = 0;
+    for line in &lines {
+        if let Some(label) = &line.label {
+            labels.insert(label.clone(), Address(address));
+        }
+        address += line.directive.width();
+    }
+
+    let mut memory = HashMap::new();
+    let mut address = 0;
+    for line in &lines {
+        match &line.directive {
+            Directive::Instruction { opcode, operands } => {
+                let modes = operands
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (index, operand)| {
+                        acc + operand.mode().digit() * 10i32.pow(index as u32)
+                    });
+                memory.insert(Address(address), Value(opcode + modes * 100));
+
+                for (index, operand) in operands.iter().enumerate() {
+                    let value = resolve_operand(operand, &labels, line.number)?;
+                    memory.insert(Address(address + 1 + index as u32), Value(value));
+                }
+            }
+            Directive::Data(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    memory.insert(Address(address + index as u32), Value(*value));
+                }
+            }
+        }
+        address += line.directive.width();
+    }
+
+    Ok(Memory::new(memory))
+}
+
+fn resolve_operand(
+    operand: &Operand,
+    labels: &HashMap<String, Address>,
+    line: usize,
+) -> Result<i32, Error> {
+    match operand {
+        Operand::Literal(_, value) => Ok(*value),
+        Operand::Label(_, label) => labels
+            .get(label)
+            .map(|addr| addr.0 as i32)
+            .ok_or_else(|| Error::UnknownLabel {
+                line,
+                label: label.clone(),
+            }),
+    }
+}
+
+fn mnemonic_info(mnemonic: &str) -> Option<(i32, usize)> {
+    match mnemonic {
+        "ADD" => Some((1, 3)),
+        "MUL" => Some((2, 3)),
+        "IN" => Some((3, 1)),
+        "OUT" => Some((4, 1)),
+        "JIT" => Some((5, 2)),
+        "JIF" => Some((6, 2)),
+        "LT" => Some((7, 3)),
+        "EQ" => Some((8, 3)),
+        "ARB" => Some((9, 1)),
+        "HALT" => Some((99, 0)),
+        _ => None,
+    }
+}
+
+fn parse_lines(source: &str) -> Result<Vec<Line>, Error> {
+    let mut lines = Vec::new();
+
+    for (index, raw_line) in source.lines().enumerate() {
+        let number = index + 1;
+        let mut text = raw_line.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let mut label = None;
+        if let Some(colon) = text.find(':') {
+            label = Some(text[..colon].trim().to_string());
+            text = text[colon + 1..].trim();
+        }
+        if text.is_empty() {
+            lines.push(Line {
+                number,
+                label,
+                directive: Directive::Data(Vec::new()),
+            });
+            continue;
+        }
+
+        let mut parts = text.splitn(2, char::is_whitespace);
+        let mnemonic = parts.next().unwrap().to_ascii_uppercase();
+        let rest = parts.next().unwrap_or("").trim();
+
+        let directive = if mnemonic == "DATA" {
+            let values = rest
+                .split(',')
+                .map(|token| parse_data_value(token.trim(), number))
+                .collect::<Result<Vec<_>, _>>()?;
+            Directive::Data(values)
+        } else {
+            let (opcode, expected_operands) =
+                mnemonic_info(&mnemonic).ok_or_else(|| Error::UnknownMnemonic {
+                    line: number,
+                    mnemonic: mnemonic.clone(),
+                })?;
+
+            let operands = if rest.is_empty() {
+                Vec::new()
+            } else {
+                rest.split(',')
+                    .map(|token| parse_operand(token.trim(), number))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+
+            if operands.len() != expected_operands {
+                return Err(Error::WrongOperandCount {
+                    line: number,
+                    expected: expected_operands,
+                    found: operands.len(),
+                });
+            }
+
+            Directive::Instruction { opcode, operands }
+        };
+
+        lines.push(Line {
+            number,
+            label,
+            directive,
+        });
+    }
+
+    Ok(lines)
+}
+
+fn parse_operand(token: &str, line: usize) -> Result<Operand, Error> {
+    let (mode, rest) = match token.chars().next() {
+        Some('#') => (OperandMode::Immediate, &token[1..]),
+        Some('~') => (OperandMode::Relative, &token[1..]),
+        _ => (OperandMode::Positional, token),
+    };
+
+    if let Ok(value) = rest.parse::<i32>() {
+        return Ok(Operand::Literal(mode, value));
+    }
+    if is_label(rest) {
+        return Ok(Operand::Label(mode, rest.to_string()));
+    }
+    Err(Error::InvalidOperand {
+        line,
+        operand: token.to_string(),
+    })
+}
+
+fn parse_data_value(token: &str, line: usize) -> Result<i32, Error> {
+    token.parse::<i32>().map_err(|_| Error::InvalidOperand {
+        line,
+        operand: token.to_string(),
+    })
+}
+
+fn is_label(token: &str) -> bool {
+    let mut chars = token.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn assembles_an_instruction() {
+        let memory = assemble("ADD 10, 20, 100\nHALT").unwrap();
+
+        assert_eq!(memory, Memory::from_program("1,10,20,100,99").unwrap());
+    }
+
+    #[test]
+    fn assembles_immediate_and_relative_operands() {
+        let memory = assemble("ADD #5, #1, ~100\nHALT").unwrap();
+
+        assert_eq!(memory, Memory::from_program("21101,5,1,100,99").unwrap());
+    }
+
+    #[test]
+    fn assembles_data() {
+        let memory = assemble("DATA 1, 2, 3").unwrap();
+
+        assert_eq!(memory, Memory::from_program("1,2,3").unwrap());
+    }
+
+    #[test]
+    fn resolves_labels_to_their_address() {
+        let memory =
+            assemble("loop: ADD 10, 20, 100\n    JIT 1, loop\n    HALT\nres: DATA 0").unwrap();
+
+        assert_eq!(memory.read(Address(0)).unwrap(), Value(1));
+        assert_eq!(memory.read(Address(5)).unwrap(), Value(1));
+        assert_eq!(memory.read(Address(6)).unwrap(), Value(0)); // `loop` resolves to address 0
+        assert_eq!(memory.read(Address(8)).unwrap(), Value(0));
+    }
+
+    #[test]
+    fn rejects_unknown_mnemonics() {
+        let err = assemble("NOPE 1, 2, 3").unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::UnknownMnemonic {
+                line: 1,
+                mnemonic: "NOPE".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_operand_count() {
+        let err = assemble("ADD 1, 2").unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::WrongOperandCount {
+                line: 1,
+                expected: 3,
+                found: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_labels() {
+        let err = assemble("JIT 1, nowhere\nHALT").unwrap_err();
+
+        assert_eq!(
+            err,
+            Error::UnknownLabel {
+                line: 1,
+                label: "nowhere".to_string(),
+            }
+        );
+    }
+}
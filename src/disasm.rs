@@ -0,0 +1,262 @@
+use std::fmt;
+
+use crate::memory::{self, Address, Bus, Value};
+use crate::processor::{split_modes, Mode, Modes};
+
+use derive_more::From;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Param {
+    Positional(Value),
+    Immediate(Value),
+    Relative(Value),
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Param::Positional(val) => write!(f, "[{}]", val),
+            Param::Immediate(val) => write!(f, "#{}", val),
+            Param::Relative(val) => write!(f, "[rb+{}]", val),
+        }
+    }
+}
+
+impl From<(Mode, Value)> for Param {
+    fn from((mode, value): (Mode, Value)) -> Self {
+        match mode {
+            Mode::Positional => Param::Positional(value),
+            Mode::Immediate => Param::Immediate(value),
+            Mode::Relative => Param::Relative(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Instruction {
+    Add(Param, Param, Param),
+    Multiply(Param, Param, Param),
+    Input(Param),
+    Output(Param),
+    JumpIfTrue(Param, Param),
+    JumpIfFalse(Param, Param),
+    LessThan(Param, Param, Param),
+    Equals(Param, Param, Param),
+    AdjustRelativeBase(Param),
+    Halt,
+}
+
+impl Instruction {
+    /// Number of memory cells this instruction occupies, opcode word included.
+    pub fn width(&self) -> u32 {
+        match self {
+            Instruction::Add(..)
+            | Instruction::Multiply(..)
+            | Instruction::LessThan(..)
+            | Instruction::Equals(..) => 4,
+            Instruction::JumpIfTrue(..) | Instruction::JumpIfFalse(..) => 3,
+            Instruction::Input(_) | Instruction::Output(_) | Instruction::AdjustRelativeBase(_) => 2,
+            Instruction::Halt => 1,
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Add(a, b, out) => write!(f, "ADD {} {} -> {}", a, b, out),
+            Instruction::Multiply(a, b, out) => write!(f, "MUL {} {} -> {}", a, b, out),
+            Instruction::Input(out) => write!(f, "IN -> {}", out),
+            Instruction::Output(a) => write!(f, "OUT {}", a),
+            Instruction::JumpIfTrue(a, target) => write!(f, "JIT {} {}", a, target),
+            Instruction::JumpIfFalse(a, target) => write!(f, "JIF {} {}", a, target),
+            Instruction::LessThan(a, b, out) => write!(f, "LT {} {} -> {}", a, b, out),
+            Instruction::Equals(a, b, out) => write!(f, "EQ {} {} -> {}", a, b, out),
+            Instruction::AdjustRelativeBase(a) => write!(f, "ARB {}", a),
+            Instruction::Halt => write!(f, "HALT"),
+        }
+    }
+}
+
+#[derive(Debug, From, Eq, PartialEq)]
+pub enum Error {
+    Memory(memory::Error),
+    InvalidOpcode(Value),
+    IllegalMode,
+}
+
+pub fn decode_one<M: Bus>(memory: &M, addr: Address) -> Result<Instruction, Error> {
+    let modes_and_opcode = memory.read(addr)?;
+    let modes: Modes = (modes_and_opcode.0 / 100).into();
+    let opcode = modes_and_opcode.0 % 100;
+
+    let instruction = match opcode {
+        1 => {
+            let [a, b, out] = params::<3, _>(memory, addr, modes)?;
+            Instruction::Add(a, b, out)
+        }
+        2 => {
+            let [a, b, out] = params::<3, _>(memory, addr, modes)?;
+            Instruction::Multiply(a, b, out)
+        }
+        3 => {
+            let [out] = params::<1, _>(memory, addr, modes)?;
+            Instruction::Input(out)
+        }
+        4 => {
+            let [a] = params::<1, _>(memory, addr, modes)?;
+            Instruction::Output(a)
+        }
+        5 => {
+            let [a, target] = params::<2, _>(memory, addr, modes)?;
+            Instruction::JumpIfTrue(a, target)
+        }
+        6 => {
+            let [a, target] = params::<2, _>(memory, addr, modes)?;
+            Instruction::JumpIfFalse(a, target)
+        }
+        7 => {
+            let [a, b, out] = params::<3, _>(memory, addr, modes)?;
+            Instruction::LessThan(a, b, out)
+        }
+        8 => {
+            let [a, b, out] = params::<3, _>(memory, addr, modes)?;
+            Instruction::Equals(a, b, out)
+        }
+        9 => {
+            let [a] = params::<1, _>(memory, addr, modes)?;
+            Instruction::AdjustRelativeBase(a)
+        }
+        99 => Instruction::Halt,
+        _ => return Err(Error::InvalidOpcode(modes_and_opcode)),
+    };
+    Ok(instruction)
+}
+
+fn params<const N: usize, M: Bus>(
+    memory: &M,
+    addr: Address,
+    modes: Modes,
+) -> Result<[Param; N], Error> {
+    let modes = split_modes::<N>(modes).map_err(|_| Error::IllegalMode)?;
+
+    let mut out = [None; N];
+    for index in 0..N {
+        let value = memory.read(Address(addr.0 + 1 + index as u32))?;
+        out[index] = Some(Param::from((modes[index], value)));
+    }
+    Ok(out.map(Option::unwrap))
+}
+
+pub struct Disassembly<'a, M> {
+    memory: &'a M,
+    addr: Address,
+    done: bool,
+}
+
+pub fn disassemble<M: Bus>(memory: &M, start: Address) -> Disassembly<'_, M> {
+    Disassembly {
+        memory,
+        addr: start,
+        done: false,
+    }
+}
+
+impl<'a, M: Bus> Iterator for Disassembly<'a, M> {
+    type Item = Result<(Address, Instruction), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let addr = self.addr;
+        match decode_one(self.memory, addr) {
+            Ok(instruction) => {
+                if instruction == Instruction::Halt {
+                    self.done = true;
+                } else {
+                    self.addr = Address(addr.0 + instruction.width());
+                }
+                Some(Ok((addr, instruction)))
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memory::Memory;
+
+    #[test]
+    fn decode_add() {
+        let memory = Memory::from_program("1,10,20,100,99").unwrap();
+
+        let instruction = decode_one(&memory, Address(0)).unwrap();
+
+        assert_eq!(
+            instruction,
+            Instruction::Add(
+                Param::Positional(Value(10)),
+                Param::Positional(Value(20)),
+                Param::Positional(Value(100)),
+            )
+        );
+        assert_eq!(instruction.to_string(), "ADD [10] [20] -> [100]");
+    }
+
+    #[test]
+    fn decode_immediate_and_relative_modes() {
+        let memory = Memory::from_program("21101,5,1,100,99").unwrap();
+
+        let instruction = decode_one(&memory, Address(0)).unwrap();
+
+        assert_eq!(
+            instruction,
+            Instruction::Add(
+                Param::Immediate(Value(5)),
+                Param::Immediate(Value(1)),
+                Param::Relative(Value(100)),
+            )
+        );
+    }
+
+    #[test]
+    fn disassemble_whole_program() {
+        let memory = Memory::from_program("1,10,20,100,99").unwrap();
+
+        let listing: Vec<_> = disassemble(&memory, Address(0))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            listing,
+            vec![
+                (
+                    Address(0),
+                    Instruction::Add(
+                        Param::Positional(Value(10)),
+                        Param::Positional(Value(20)),
+                        Param::Positional(Value(100)),
+                    )
+                ),
+                (Address(4), Instruction::Halt),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_invalid_opcode() {
+        let memory = Memory::from_program("45").unwrap();
+
+        assert_eq!(
+            decode_one(&memory, Address(0)).unwrap_err(),
+            Error::InvalidOpcode(Value(45))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::memory::Value;
+
+pub trait Input {
+    fn read(&mut self) -> Option<Value>;
+}
+
+pub trait Output {
+    fn write(&mut self, value: Value);
+}
+
+impl Input for VecDeque<Value> {
+    fn read(&mut self) -> Option<Value> {
+        self.pop_front()
+    }
+}
+
+impl Output for VecDeque<Value> {
+    fn write(&mut self, value: Value) {
+        self.push_back(value)
+    }
+}
+
+/// `Vec::remove(0)` shifts every remaining element down, so reading drains the
+/// whole `Vec` in O(n^2). Fine for tests and one-shot scripts; prefer
+/// `VecDeque<Value>` or `Pipe` for anything that reads in a loop.
+impl Input for Vec<Value> {
+    fn read(&mut self) -> Option<Value> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+}
+
+impl Output for Vec<Value> {
+    fn write(&mut self, value: Value) {
+        self.push(value)
+    }
+}
+
+/// A queue shared between two endpoints, so that one processor's `Output` port
+/// can be wired up as another's `Input` port.
+#[derive(Debug, Clone, Default)]
+pub struct Pipe(Rc<RefCell<VecDeque<Value>>>);
+
+impl Pipe {
+    pub fn new() -> Self {
+        Pipe(Rc::new(RefCell::new(VecDeque::new())))
+    }
+}
+
+impl Input for Pipe {
+    fn read(&mut self) -> Option<Value> {
+        self.0.borrow_mut().pop_front()
+    }
+}
+
+impl Output for Pipe {
+    fn write(&mut self, value: Value) {
+        self.0.borrow_mut().push_back(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vec_deque_ports() {
+        let mut queue = VecDeque::new();
+        queue.write(Value(1));
+        queue.write(Value(2));
+
+        assert_eq!(queue.read(), Some(Value(1)));
+        assert_eq!(queue.read(), Some(Value(2)));
+        assert_eq!(queue.read(), None);
+    }
+
+    #[test]
+    fn vec_ports() {
+        let mut queue = vec![];
+        queue.write(Value(1));
+        queue.write(Value(2));
+
+        assert_eq!(queue.read(), Some(Value(1)));
+        assert_eq!(queue.read(), Some(Value(2)));
+        assert_eq!(queue.read(), None);
+    }
+
+    #[test]
+    fn pipe_shares_its_queue_across_clones() {
+        let mut writer = Pipe::new();
+        let mut reader = writer.clone();
+
+        writer.write(Value(42));
+
+        assert_eq!(reader.read(), Some(Value(42)));
+        assert_eq!(reader.read(), None);
+    }
+}
@@ -2,8 +2,12 @@
 #![feature(array_map)]
 #![feature(iter_map_while)]
 
+pub mod asm;
+pub mod disasm;
+pub mod io;
 pub mod memory;
 pub mod processor;
 
-pub use memory::{Address, Memory, Value};
+pub use io::{Input, Output, Pipe};
+pub use memory::{Address, Bus, Memory, Value, VecMemory};
 pub use processor::{Error, Processor};
@@ -1,13 +1,16 @@
-use super::{Error, Mode, Modes, Processor};
-use crate::memory::{Address, Value};
+use super::{split_modes, Error, Mode, Modes, Processor, ProcessorState};
+use crate::io::{Input, Output};
+use crate::memory::{Address, Bus, Value};
 
 use std::convert::{TryFrom, TryInto};
 
 type OpcodeResult = Result<Option<Value>, Error>;
 
-impl<I> Processor<I>
+impl<I, O, M> Processor<I, O, M>
 where
-    I: Iterator<Item = Value>,
+    I: Input,
+    O: Output,
+    M: Bus,
 {
     pub(super) fn add(&mut self, modes: Modes) -> OpcodeResult {
         let modes = split_modes::<3>(modes)?;
@@ -24,27 +27,36 @@ where
         let modes = split_modes::<3>(modes)?;
         let args = self.read_arguments::<2>(&modes)?;
 
-        let res = args[0] * args[1];
+        let res = Value(args[0].0 * args[1].0);
 
         self.write_result(&modes, 2, res)?;
         self.pc = Address(self.pc.0 + 4);
         Ok(None)
     }
 
-    pub(super) fn input(&mut self, modes: Modes) -> OpcodeResult {
-        let modes = split_modes::<1>(modes)?;
+    pub(super) fn input(&mut self, modes: Modes) -> ProcessorState {
+        let modes = match split_modes::<1>(modes) {
+            Ok(modes) => modes,
+            Err(err) => return ProcessorState::Error(err),
+        };
 
-        let res = self.input.next().ok_or(Error::InputReadError)?;
+        let value = match self.input.read() {
+            Some(value) => value,
+            None => return ProcessorState::NeedsInput,
+        };
 
-        self.write_result(&modes, 0, res)?;
+        if let Err(err) = self.write_result(&modes, 0, value) {
+            return ProcessorState::Error(err);
+        }
         self.pc = Address(self.pc.0 + 2);
-        Ok(None)
+        ProcessorState::Continue(None)
     }
 
     pub(super) fn output(&mut self, modes: Modes) -> OpcodeResult {
         let modes = split_modes::<1>(modes)?;
         let args = self.read_arguments::<1>(&modes)?;
 
+        self.output.write(args[0]);
         self.pc = Address(self.pc.0 + 2);
         Ok(Some(args[0]))
     }
@@ -121,7 +133,7 @@ where
     ) -> Result<(), Error> {
         let mode = modes[arg_index];
         let out_arg_addr = Address(self.pc.0 + 1 + u32::try_from(arg_index).unwrap());
-        let immediate_out = self.memory.read(out_arg_addr);
+        let immediate_out = self.memory.read(out_arg_addr)?;
         match mode {
             Mode::Positional => self.memory.write(immediate_out.try_into()?, value),
             Mode::Immediate => self.memory.write(out_arg_addr, value),
@@ -134,28 +146,12 @@ where
 
     fn read_argument(&self, mode: Mode, index: Address) -> Result<Value, Error> {
         let addr = Address(self.pc.0 + 1 + index.0);
-        let addr2 = self.memory.read(addr);
+        let addr2 = self.memory.read(addr)?;
         let out = match mode {
-            Mode::Positional => self.memory.read(addr2.try_into()?),
+            Mode::Positional => self.memory.read(addr2.try_into()?)?,
             Mode::Immediate => addr2,
-            Mode::Relative => self.memory.read((self.relative_base + addr2).try_into()?),
+            Mode::Relative => self.memory.read((self.relative_base + addr2).try_into()?)?,
         };
         Ok(out)
     }
 }
-
-fn split_modes<const N: usize>(num: Modes) -> Result<[Mode; N], Error> {
-    let mut out: [Option<Mode>; N] = [None; N];
-
-    let mut modulor = 10;
-    let mut divisor = 1;
-    for index in 0..N {
-        let mode = (num % modulor) / divisor;
-        modulor *= 10;
-        divisor *= 10;
-
-        out[index] = Some(mode.try_into().or(Err(Error::IllegalMode))?);
-    }
-    let out = out.map(Option::unwrap);
-    Ok(out)
-}
@@ -1,39 +1,79 @@
 mod handlers;
+mod trace;
 
-use std::{collections::VecDeque, convert::TryFrom};
+use std::{
+    collections::VecDeque,
+    convert::{TryFrom, TryInto},
+};
 
-use crate::memory::{self, Address, Memory, Value};
+use crate::io::{Input, Output};
+use crate::memory::{self, Address, Bus, Memory, Value};
 
 use derive_more::From;
+use trace::TraceHook;
+
+pub use trace::{Step, TraceEvent};
 
 #[derive(Debug, Clone)]
-pub struct Processor {
+pub struct Processor<I = VecDeque<Value>, O = VecDeque<Value>, M = Memory> {
     pc: Address,
     relative_base: Value,
-    memory: Memory,
-    input_buffer: VecDeque<Value>,
+    memory: M,
+    input: I,
+    output: O,
+    trace: TraceHook,
 }
 
-impl Processor {
+impl Processor<VecDeque<Value>, VecDeque<Value>, Memory> {
     pub fn new(memory: Memory) -> Self {
+        Processor::with_io(memory, VecDeque::new(), VecDeque::new())
+    }
+}
+
+impl<I, O, M> Processor<I, O, M>
+where
+    I: Input,
+    O: Output,
+    M: Bus,
+{
+    pub fn with_io(memory: M, input: I, output: O) -> Self {
         Processor {
             pc: Address(0),
             relative_base: Value(0),
             memory,
-            input_buffer: VecDeque::new(),
+            input,
+            output,
+            trace: TraceHook::default(),
         }
     }
 
     pub fn execute_once(&mut self) -> ProcessorState {
-        let modes_and_opcode = self.memory.read(self.pc);
+        let pending_trace = self.prepare_trace_if_enabled(self.pc);
+
+        let state = self.dispatch();
+
+        if let Some(pending) = pending_trace {
+            if matches!(state, ProcessorState::Continue(_) | ProcessorState::Terminate) {
+                self.emit_trace(pending);
+            }
+        }
+
+        state
+    }
 
-        let modes = modes_and_opcode.0 / 100;
+    fn dispatch(&mut self) -> ProcessorState {
+        let modes_and_opcode = match self.memory.read(self.pc) {
+            Ok(value) => value,
+            Err(err) => return ProcessorState::Error(err.into()),
+        };
+
+        let modes = Modes::from(modes_and_opcode.0 / 100);
         let opcode = Value(modes_and_opcode.0 % 100);
 
         let result = match opcode.0 {
             1 => self.add(modes),
             2 => self.multiply(modes),
-            3 => self.input(modes),
+            3 => return self.input(modes),
             4 => self.output(modes),
             5 => self.jump_if_true(modes),
             6 => self.jump_if_false(modes),
@@ -46,7 +86,7 @@ impl Processor {
         };
         match result {
             Ok(opt_out) => ProcessorState::Continue(opt_out),
-            Err(err) => return ProcessorState::Error(err),
+            Err(err) => ProcessorState::Error(err),
         }
     }
 
@@ -54,6 +94,7 @@ impl Processor {
         loop {
             match self.execute_once() {
                 ProcessorState::Continue(_) => continue,
+                ProcessorState::NeedsInput => break Err(Error::InputReadError),
                 ProcessorState::Terminate => break Ok(()),
                 ProcessorState::Error(err) => break Err(err),
             }
@@ -67,33 +108,63 @@ impl Processor {
                     Some(out) => break Some(Ok(out)),
                     None => continue,
                 },
+                ProcessorState::NeedsInput => break Some(Err(Error::InputReadError)),
                 ProcessorState::Terminate => break None,
                 ProcessorState::Error(err) => break Some(Err(err)),
             }
         }
     }
 
-    pub fn push_input(&mut self, value: Value) {
-        self.input_buffer.push_back(value)
+    /// Like `execute_until_output`, but suspends with `ExecutionOutcome::NeedsInput`
+    /// instead of erroring when the input buffer runs dry, so the caller can
+    /// `push_input` and resume execution from the same instruction.
+    pub fn execute_until_input_or_output(&mut self) -> Result<ExecutionOutcome, Error> {
+        loop {
+            match self.execute_once() {
+                ProcessorState::Continue(opt_out) => match opt_out {
+                    Some(out) => break Ok(ExecutionOutcome::Output(out)),
+                    None => continue,
+                },
+                ProcessorState::NeedsInput => break Ok(ExecutionOutcome::NeedsInput),
+                ProcessorState::Terminate => break Ok(ExecutionOutcome::Terminate),
+                ProcessorState::Error(err) => break Err(err),
+            }
+        }
+    }
+
+    pub fn push_input(&mut self, value: Value)
+    where
+        I: Output,
+    {
+        self.input.write(value)
     }
 }
 
 pub enum ProcessorState {
     Continue(Option<Value>),
+    NeedsInput,
     Terminate,
     Error(Error),
 }
 
+#[derive(Debug, Eq, PartialEq)]
+pub enum ExecutionOutcome {
+    Output(Value),
+    NeedsInput,
+    Terminate,
+}
+
 #[derive(Debug, From, Eq, PartialEq)]
 pub enum Error {
     IllegalPositionalArgument(memory::TryFromValueError),
+    Memory(memory::Error),
     IllegalMode,
     InputReadError,
     InvalidOpcode,
     FinishedWithoutTerminating,
 }
 
-type Modes = i64;
+pub(crate) type Modes = i64;
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum Mode {
@@ -119,14 +190,30 @@ pub enum ModeTryFromError {
     InvalidMode,
 }
 
+pub(crate) fn split_modes<const N: usize>(num: Modes) -> Result<[Mode; N], Error> {
+    let mut out: [Option<Mode>; N] = [None; N];
+
+    let mut modulor = 10;
+    let mut divisor = 1;
+    for index in 0..N {
+        let mode = (num % modulor) / divisor;
+        modulor *= 10;
+        divisor *= 10;
+
+        out[index] = Some(mode.try_into().or(Err(Error::IllegalMode))?);
+    }
+    let out = out.map(Option::unwrap);
+    Ok(out)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::memory::{Address, Value};
     use maplit::hashmap;
 
-    impl Processor {
-        fn get_memory(&self) -> &Memory {
+    impl<I, O, M> Processor<I, O, M> {
+        fn get_memory(&self) -> &M {
             &self.memory
         }
     }
@@ -138,7 +225,7 @@ mod test {
                 $(
                     Address($key) => Value($value),
                 )*
-            };
+            }
         };
     }
 
@@ -220,9 +307,11 @@ mod test {
             2 => 3,
             3 => 100,
         });
+        // No 99 anywhere in the program, so pc runs off the end of the
+        // sparse `Memory` and hits an unset cell rather than an explicit 0.
         assert_eq!(
             Processor::new(memory).execute(),
-            Err(Error::FinishedWithoutTerminating)
+            Err(Error::Memory(memory::Error::EmptyRead(Address(4))))
         );
     }
 
@@ -270,6 +359,29 @@ mod test {
         processor.execute().unwrap();
     }
 
+    #[test]
+    fn needs_input() {
+        let memory = Memory::new(memory! {
+            0 => 3,
+            1 => 0,
+            2 => 99,
+        });
+
+        let mut processor = Processor::new(memory);
+
+        assert_eq!(
+            processor.execute_until_input_or_output().unwrap(),
+            ExecutionOutcome::NeedsInput
+        );
+
+        processor.push_input(Value(42));
+
+        assert_eq!(
+            processor.execute_until_input_or_output().unwrap(),
+            ExecutionOutcome::Terminate
+        );
+    }
+
     #[test]
     fn output() {
         let memory = Memory::new(memory! {
@@ -286,6 +398,51 @@ mod test {
         assert_eq!(term, None);
     }
 
+    #[test]
+    fn piped_processors() {
+        use crate::io::Pipe;
+
+        let increment = || {
+            Memory::new(memory! {
+                0 => 3, //IN -> addr10
+                1 => 10,
+                2 => 1, //ADD addr10 addr11 -> addr10
+                3 => 10,
+                4 => 11,
+                5 => 10,
+                6 => 4, //OUT addr10
+                7 => 10,
+                8 => 99,
+                10 => 0,
+                11 => 1,
+            })
+        };
+
+        let pipe = Pipe::new();
+        let mut producer = Processor::with_io(increment(), VecDeque::new(), pipe.clone());
+        let mut consumer = Processor::with_io(increment(), pipe, VecDeque::new());
+
+        producer.push_input(Value(5));
+        producer.execute().unwrap();
+        consumer.execute().unwrap();
+
+        assert_eq!(producer.get_memory().read(Address(10)).unwrap(), Value(6));
+        assert_eq!(consumer.get_memory().read(Address(10)).unwrap(), Value(7));
+    }
+
+    #[test]
+    fn vec_memory_backend() {
+        use crate::memory::VecMemory;
+
+        // addr10, addr20 and addr100 are all beyond the 5-cell program, so they
+        // read and grow as Value(0) rather than erroring.
+        let memory = VecMemory::new(vec![Value(1), Value(10), Value(20), Value(100), Value(99)]);
+        let mut processor = Processor::with_io(memory, VecDeque::new(), VecDeque::new());
+        processor.execute().unwrap();
+
+        assert_eq!(processor.get_memory().read(Address(100)).unwrap(), Value(0));
+    }
+
     #[test]
     fn jump_if_true() {
         let memory = Memory::new(memory! {
@@ -387,4 +544,51 @@ mod test {
 
         Processor::new(memory).execute().unwrap();
     }
+
+    #[test]
+    fn step_decodes_without_executing() {
+        use crate::disasm::{Instruction, Param};
+
+        let memory = Memory::from_program("1,9,10,3,2,3,11,0,99,30,40,50").unwrap();
+        let processor = Processor::new(memory);
+
+        let step = processor.step().unwrap();
+
+        assert_eq!(step.pc, Address(0));
+        assert_eq!(step.relative_base, Value(0));
+        assert_eq!(
+            step.instruction,
+            Instruction::Add(
+                Param::Positional(Value(9)),
+                Param::Positional(Value(10)),
+                Param::Positional(Value(3)),
+            )
+        );
+    }
+
+    #[test]
+    fn trace_records_resolved_args_and_writes() {
+        use crate::disasm::Instruction;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let memory = Memory::from_program("1,9,10,3,2,3,11,0,99,30,40,50").unwrap();
+        let mut processor = Processor::new(memory);
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&events);
+        processor.set_trace(move |event| recorded.borrow_mut().push(event.clone()));
+
+        processor.execute().unwrap();
+
+        let events = events.borrow();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].address, Address(0));
+        assert_eq!(events[0].args, vec![Value(30), Value(40)]);
+        assert_eq!(events[0].write, Some((Address(3), Value(70))));
+        assert_eq!(events[1].args, vec![Value(70), Value(50)]);
+        assert_eq!(events[1].write, Some((Address(0), Value(3500))));
+        assert_eq!(events[2].instruction, Instruction::Halt);
+        assert_eq!(events[2].write, None);
+    }
 }
@@ -0,0 +1,170 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::disasm::{self, Instruction, Param};
+use crate::io::{Input, Output};
+use crate::memory::{Address, Bus, Value};
+
+use super::{Error, Processor};
+
+/// A decoded instruction that just ran, as reported to a callback installed
+/// with `Processor::set_trace`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub address: Address,
+    pub instruction: Instruction,
+    pub args: Vec<Value>,
+    pub write: Option<(Address, Value)>,
+}
+
+/// A snapshot of the instruction `Processor` is about to execute, without
+/// running it. See `Processor::step`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Step {
+    pub pc: Address,
+    pub relative_base: Value,
+    pub instruction: Instruction,
+}
+
+impl<I, O, M> Processor<I, O, M>
+where
+    I: Input,
+    O: Output,
+    M: Bus,
+{
+    /// Decodes the instruction at `pc` without executing it, for inspection
+    /// or breakpointing ahead of a call to `execute_once`.
+    pub fn step(&self) -> Result<Step, disasm::Error> {
+        let instruction = disasm::decode_one(&self.memory, self.pc)?;
+        Ok(Step {
+            pc: self.pc,
+            relative_base: self.relative_base,
+            instruction,
+        })
+    }
+
+    /// Installs a callback invoked with a `TraceEvent` once per opcode that
+    /// actually runs (not on `NeedsInput` suspension or an error). Useful for
+    /// logging execution, counting instructions, or breaking on a target
+    /// `Address`. Replaces any previously installed callback.
+    pub fn set_trace(&mut self, trace: impl FnMut(&TraceEvent) + 'static) {
+        self.trace.0 = Some(Box::new(trace));
+    }
+
+    /// Removes a previously installed trace callback.
+    pub fn clear_trace(&mut self) {
+        self.trace.0 = None;
+    }
+
+    pub(super) fn prepare_trace_if_enabled(&self, pc: Address) -> Option<PendingTrace> {
+        self.trace.0.as_ref()?;
+        self.prepare_trace(pc)
+    }
+
+    fn prepare_trace(&self, address: Address) -> Option<PendingTrace> {
+        let instruction = disasm::decode_one(&self.memory, address).ok()?;
+        let (arg_params, write_param) = operand_params(&instruction);
+
+        let mut args = Vec::with_capacity(arg_params.len());
+        for param in arg_params {
+            args.push(self.resolve(param).ok()?);
+        }
+        let write_target = write_param.and_then(|param| self.write_target(param).ok());
+
+        Some(PendingTrace {
+            address,
+            instruction,
+            args,
+            write_target,
+        })
+    }
+
+    pub(super) fn emit_trace(&mut self, pending: PendingTrace) {
+        let write = pending
+            .write_target
+            .and_then(|addr| self.memory.read(addr).ok().map(|value| (addr, value)));
+
+        let event = TraceEvent {
+            address: pending.address,
+            instruction: pending.instruction,
+            args: pending.args,
+            write,
+        };
+        if let Some(trace) = &mut self.trace.0 {
+            trace(&event);
+        }
+    }
+
+    fn resolve(&self, param: Param) -> Result<Value, Error> {
+        match param {
+            Param::Immediate(value) => Ok(value),
+            Param::Positional(addr) => Ok(self.memory.read(Address::try_from(addr)?)?),
+            Param::Relative(offset) => {
+                let addr = Value(self.relative_base.0 + offset.0);
+                Ok(self.memory.read(Address::try_from(addr)?)?)
+            }
+        }
+    }
+
+    fn write_target(&self, param: Param) -> Result<Address, Error> {
+        match param {
+            Param::Positional(addr) => Ok(Address::try_from(addr)?),
+            Param::Relative(offset) => {
+                let addr = Value(self.relative_base.0 + offset.0);
+                Ok(Address::try_from(addr)?)
+            }
+            // `write_result` treats an immediate-mode destination as writing to
+            // the parameter's own cell rather than indirecting through it; too
+            // unusual a case to report a resolved write address for.
+            Param::Immediate(_) => Err(Error::IllegalMode),
+        }
+    }
+}
+
+pub(super) struct PendingTrace {
+    address: Address,
+    instruction: Instruction,
+    args: Vec<Value>,
+    write_target: Option<Address>,
+}
+
+/// Splits a decoded `Instruction` into the params read as arguments and the
+/// param (if any) written as its result, so tracing can resolve both without
+/// touching the opcode handlers.
+fn operand_params(instruction: &Instruction) -> (Vec<Param>, Option<Param>) {
+    use Instruction::*;
+
+    match *instruction {
+        Add(a, b, out) | Multiply(a, b, out) | LessThan(a, b, out) | Equals(a, b, out) => {
+            (vec![a, b], Some(out))
+        }
+        Input(out) => (vec![], Some(out)),
+        Output(a) => (vec![a], None),
+        JumpIfTrue(a, target) | JumpIfFalse(a, target) => (vec![a, target], None),
+        AdjustRelativeBase(a) => (vec![a], None),
+        Halt => (vec![], None),
+    }
+}
+
+type TraceCallback = Box<dyn FnMut(&TraceEvent)>;
+
+/// Holds the optional `set_trace` callback. Wrapped in its own type so that
+/// `Processor` can keep deriving `Debug`/`Clone`: the callback is opaque and
+/// intentionally dropped on clone rather than duplicated.
+#[derive(Default)]
+pub(super) struct TraceHook(Option<TraceCallback>);
+
+impl fmt::Debug for TraceHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(_) => f.write_str("TraceHook(Some(_))"),
+            None => f.write_str("TraceHook(None)"),
+        }
+    }
+}
+
+impl Clone for TraceHook {
+    fn clone(&self) -> Self {
+        TraceHook(None)
+    }
+}